@@ -1,5 +1,9 @@
+use std::collections::HashMap;
+
 use crate::error::Result;
-use git2::Repository;
+use git2::{
+	BranchType, Config, Repository, StatusOptions, StatusShow,
+};
 use scopetime::scope_time;
 use serde::{Deserialize, Serialize};
 
@@ -112,6 +116,302 @@ pub fn push_default_strategy_config_repo(
 	)
 }
 
+/// a local ref to push to a remote ref, with the force flag the push
+/// subsystem should use for it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushRefspecPair {
+	///
+	pub local_ref: String,
+	///
+	pub remote_ref: String,
+	///
+	pub force: bool,
+}
+
+/// a concrete refspec (or set of refspecs), ready for the push subsystem
+/// to consume, that `push.default` resolved down to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PushRefspec {
+	/// a single local ref pushed to a single remote ref
+	Single(PushRefspecPair),
+	/// one pair per local branch that already has a same-named branch on
+	/// the remote
+	Matching(Vec<PushRefspecPair>),
+}
+
+fn current_branch_name(repo: &Repository) -> Result<String> {
+	let head = repo.head()?;
+
+	if !head.is_branch() {
+		return Err(crate::Error::GitConfig(
+			"cannot resolve push.default: HEAD is detached"
+				.to_string(),
+		));
+	}
+
+	head.shorthand().map(str::to_string).ok_or_else(|| {
+		crate::Error::GitConfig(
+			"cannot resolve push.default: HEAD has no name"
+				.to_string(),
+		)
+	})
+}
+
+fn resolve_current_refspec(repo: &Repository) -> Result<PushRefspec> {
+	let branch = current_branch_name(repo)?;
+
+	Ok(PushRefspec::Single(PushRefspecPair {
+		local_ref: format!("refs/heads/{branch}"),
+		remote_ref: format!("refs/heads/{branch}"),
+		force: false,
+	}))
+}
+
+fn resolve_upstream_refspec(
+	repo: &Repository,
+	remote_name: &str,
+) -> Result<PushRefspec> {
+	let branch = current_branch_name(repo)?;
+
+	let configured_remote = get_config_string_repo(
+		repo,
+		&format!("branch.{branch}.remote"),
+	)?;
+
+	if configured_remote.as_deref() != Some(remote_name) {
+		return Err(crate::Error::GitConfig(format!(
+			"push.default=upstream requires pushing to '{}', the configured upstream remote for '{branch}', not '{remote_name}'",
+			configured_remote.as_deref().unwrap_or("<none>")
+		)));
+	}
+
+	let remote_ref = get_config_string_repo(
+		repo,
+		&format!("branch.{branch}.merge"),
+	)?
+	.ok_or_else(|| {
+		crate::Error::GitConfig(format!(
+			"push.default=upstream requires 'branch.{branch}.merge' to be set"
+		))
+	})?;
+
+	Ok(PushRefspec::Single(PushRefspecPair {
+		local_ref: format!("refs/heads/{branch}"),
+		remote_ref,
+		force: false,
+	}))
+}
+
+fn resolve_simple_refspec(
+	repo: &Repository,
+	remote_name: &str,
+) -> Result<PushRefspec> {
+	let branch = current_branch_name(repo)?;
+
+	let configured_remote = get_config_string_repo(
+		repo,
+		&format!("branch.{branch}.remote"),
+	)?;
+
+	if configured_remote.as_deref() != Some(remote_name) {
+		// pushing to a remote the branch doesn't track falls back to
+		// `current` semantics
+		return resolve_current_refspec(repo);
+	}
+
+	let remote_ref = get_config_string_repo(
+		repo,
+		&format!("branch.{branch}.merge"),
+	)?
+	.ok_or_else(|| {
+		crate::Error::GitConfig(format!(
+			"push.default=simple requires 'branch.{branch}.merge' to be set"
+		))
+	})?;
+
+	let upstream_short =
+		remote_ref.strip_prefix("refs/heads/").unwrap_or(&remote_ref);
+
+	if upstream_short != branch {
+		return Err(crate::Error::GitConfig(format!(
+			"push.default=simple requires the upstream branch name ('{upstream_short}') to match the local branch name ('{branch}')"
+		)));
+	}
+
+	Ok(PushRefspec::Single(PushRefspecPair {
+		local_ref: format!("refs/heads/{branch}"),
+		remote_ref,
+		force: false,
+	}))
+}
+
+fn resolve_matching_refspec(
+	repo: &Repository,
+	remote_name: &str,
+) -> Result<PushRefspec> {
+	let mut pairs = Vec::new();
+
+	for branch in repo.branches(Some(BranchType::Local))? {
+		let (branch, _) = branch?;
+
+		let Some(name) = branch.name()? else {
+			continue;
+		};
+
+		let remote_ref_name =
+			format!("refs/remotes/{remote_name}/{name}");
+
+		if repo.find_reference(&remote_ref_name).is_ok() {
+			pairs.push(PushRefspecPair {
+				local_ref: format!("refs/heads/{name}"),
+				remote_ref: format!("refs/heads/{name}"),
+				force: false,
+			});
+		}
+	}
+
+	Ok(PushRefspec::Matching(pairs))
+}
+
+/// resolves `push.default` down to the concrete refspec(s) `git push` would
+/// use for `remote_name`, so callers don't have to re-implement the
+/// strategy semantics themselves
+pub fn resolve_push_refspec(
+	repo: &Repository,
+	remote_name: &str,
+) -> Result<Option<PushRefspec>> {
+	scope_time!("resolve_push_refspec");
+
+	match push_default_strategy_config_repo(repo)? {
+		PushDefaultStrategyConfig::Nothing => {
+			Err(crate::Error::GitConfig(
+				"push.default is 'nothing': an explicit refspec is required".to_string(),
+			))
+		}
+		PushDefaultStrategyConfig::Current => {
+			resolve_current_refspec(repo).map(Some)
+		}
+		PushDefaultStrategyConfig::Upstream => {
+			resolve_upstream_refspec(repo, remote_name).map(Some)
+		}
+		PushDefaultStrategyConfig::Simple => {
+			resolve_simple_refspec(repo, remote_name).map(Some)
+		}
+		PushDefaultStrategyConfig::Matching => {
+			resolve_matching_refspec(repo, remote_name).map(Some)
+		}
+	}
+}
+
+// see https://git-scm.com/docs/git-config#_syntax
+// "Subsection names are case sensitive [...] Section and variable names
+// are not case sensitive" - e.g. `branch.Main.remote` and
+// `branch.main.remote` are distinct keys to git, so only the section and
+// the final key component may be lowercased here
+fn normalize_config_key_case(name: &str) -> String {
+	let Some(first_dot) = name.find('.') else {
+		return name.to_ascii_lowercase();
+	};
+	let Some(last_dot) = name.rfind('.') else {
+		return name.to_ascii_lowercase();
+	};
+
+	if first_dot == last_dot {
+		// no subsection, e.g. `core.bare`
+		return name.to_ascii_lowercase();
+	}
+
+	format!(
+		"{}.{}.{}",
+		name[..first_dot].to_ascii_lowercase(),
+		&name[first_dot + 1..last_dot],
+		name[last_dot + 1..].to_ascii_lowercase()
+	)
+}
+
+/// an in-memory snapshot of a repo's config, loaded once so a burst of
+/// lookups (e.g. during a status refresh) doesn't reopen and reparse the
+/// config stack for every single key
+#[derive(Debug, Clone, Default)]
+pub struct ConfigSnapshot {
+	// keyed by normalized `section.subsection.key`, see
+	// `normalize_config_key_case`; a valueless key (e.g.
+	// `[section]\n\tflag`) is stored as `None`
+	values: HashMap<String, Option<String>>,
+}
+
+impl ConfigSnapshot {
+	/// loads every entry currently visible in `repo`'s config stack
+	pub fn new(repo: &Repository) -> Result<Self> {
+		scope_time!("ConfigSnapshot::new");
+
+		Self::from_config(&repo.config()?)
+	}
+
+	// split out so precedence across levels can be unit-tested against an
+	// isolated, hand-built `Config` instead of a real repo's global/system
+	// files
+	fn from_config(cfg: &Config) -> Result<Self> {
+		let mut values = HashMap::new();
+
+		// `entries` walks every level from least to most specific
+		// (system, global, local, ...), so folding into a map with a
+		// plain overwriting insert leaves the most specific value in
+		// place, matching how `get_entry` resolves precedence
+		let entries = cfg.entries(None)?;
+		for entry in &entries {
+			let entry = entry?;
+			if let Some(name) = entry.name() {
+				values.insert(
+					normalize_config_key_case(name),
+					entry.value().map(str::to_string),
+				);
+			}
+		}
+
+		Ok(Self { values })
+	}
+
+	/// rebuilds the snapshot from `repo`'s current config, discarding
+	/// whatever was cached before
+	pub fn refresh(&mut self, repo: &Repository) -> Result<()> {
+		*self = Self::new(repo)?;
+		Ok(())
+	}
+
+	/// looks up a `section.subsection.key`, matching git's own case
+	/// sensitivity: the section and key are case-insensitive, the
+	/// subsection (if any) is not
+	pub fn string(&self, key: &str) -> Option<&str> {
+		self.values
+			.get(&normalize_config_key_case(key))
+			.and_then(Option::as_deref)
+	}
+
+	/// looks up a key and parses it as a boolean, using the same lenient
+	/// rules as [`get_config_bool`]
+	pub fn bool(&self, key: &str) -> Option<bool> {
+		match self.values.get(&normalize_config_key_case(key)) {
+			// a valueless key, e.g. `[section]\n\tflag`, is `true`
+			Some(None) => Some(true),
+			Some(Some(raw)) => parse_config_bool(raw),
+			None => None,
+		}
+	}
+
+	/// looks up a key and parses it as an integer, honoring the `k`/`m`/`g`
+	/// suffix understood by [`get_config_int`]
+	pub fn int(&self, key: &str) -> Option<i64> {
+		self.string(key).and_then(parse_config_int)
+	}
+}
+
+/// builds a [`ConfigSnapshot`] for `repo_path`'s repo
+pub fn config_snapshot(repo_path: &RepoPath) -> Result<ConfigSnapshot> {
+	let repo = repo(repo_path)?;
+	ConfigSnapshot::new(&repo)
+}
+
 ///
 pub fn untracked_files_config(
 	repo_path: &RepoPath,
@@ -152,6 +452,380 @@ pub fn get_config_string_repo(
 	}
 }
 
+// see https://git-scm.com/docs/git-config#SCOPES
+/// the config file level (i.e. where it is defined) a value was read from,
+/// mirrors `git2::ConfigLevel`
+///
+/// worktree-level config (`GIT_CONFIG_LEVEL_WORKTREE`) isn't represented
+/// here: it needs a `git2` version new enough to expose
+/// `ConfigLevel::Worktree`, which this tree has no `Cargo.toml` to confirm
+#[derive(
+	Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub enum ConfigScope {
+	///
+	System,
+	///
+	XdgGlobal,
+	///
+	Global,
+	///
+	Local,
+}
+
+impl ConfigScope {
+	fn to_git2(self) -> git2::ConfigLevel {
+		match self {
+			Self::System => git2::ConfigLevel::System,
+			Self::XdgGlobal => git2::ConfigLevel::XDG,
+			Self::Global => git2::ConfigLevel::Global,
+			Self::Local => git2::ConfigLevel::Local,
+		}
+	}
+
+	fn from_git2(level: git2::ConfigLevel) -> Option<Self> {
+		match level {
+			git2::ConfigLevel::System => Some(Self::System),
+			git2::ConfigLevel::XDG => Some(Self::XdgGlobal),
+			git2::ConfigLevel::Global => Some(Self::Global),
+			git2::ConfigLevel::Local => Some(Self::Local),
+			_ => None,
+		}
+	}
+}
+
+/// a config value together with the level it was defined at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigEntryInfo {
+	///
+	pub value: String,
+	/// `None` if git2 reports a level we don't have a mapping for
+	pub scope: Option<ConfigScope>,
+}
+
+/// get a config value together with the level (file) that defines it
+pub fn get_config_entry(
+	repo: &Repository,
+	key: &str,
+) -> Result<Option<ConfigEntryInfo>> {
+	scope_time!("get_config_entry");
+
+	let cfg = repo.config()?;
+
+	let Ok(entry) = cfg.get_entry(key) else {
+		return Ok(None);
+	};
+
+	if !entry.has_value() {
+		return Ok(None);
+	}
+
+	Ok(entry.value().map(|value| ConfigEntryInfo {
+		value: value.to_string(),
+		scope: ConfigScope::from_git2(entry.level()),
+	}))
+}
+
+/// set a string value in the config file at the given `scope`
+///
+/// opens the config level directly so e.g. a repo-local override can be
+/// written without touching the user's global config
+pub fn set_config_string(
+	repo_path: &RepoPath,
+	key: &str,
+	value: &str,
+	scope: ConfigScope,
+) -> Result<()> {
+	let repo = repo(repo_path)?;
+	set_config_string_repo(&repo, key, value, scope)
+}
+
+///
+pub fn set_config_string_repo(
+	repo: &Repository,
+	key: &str,
+	value: &str,
+	scope: ConfigScope,
+) -> Result<()> {
+	scope_time!("set_config_string_repo");
+
+	let mut level_cfg =
+		repo.config()?.open_level(scope.to_git2())?;
+	level_cfg.set_str(key, value)?;
+
+	Ok(())
+}
+
+/// remove a value from the config file at the given `scope`
+pub fn unset_config_string(
+	repo_path: &RepoPath,
+	key: &str,
+	scope: ConfigScope,
+) -> Result<()> {
+	let repo = repo(repo_path)?;
+	unset_config_string_repo(&repo, key, scope)
+}
+
+///
+pub fn unset_config_string_repo(
+	repo: &Repository,
+	key: &str,
+	scope: ConfigScope,
+) -> Result<()> {
+	scope_time!("unset_config_string_repo");
+
+	let mut level_cfg =
+		repo.config()?.open_level(scope.to_git2())?;
+	level_cfg.remove(key)?;
+
+	Ok(())
+}
+
+// see https://git-scm.com/docs/git-config#Documentation/git-config.txt-statusshowIgnored
+/// bundles every `status.*`/`diff.renames` setting that affects what a
+/// status scan considers part of its report, beyond just
+/// `status.showUntrackedFiles`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusConfig {
+	/// `status.showUntrackedFiles`
+	pub show_untracked_files: ShowUntrackedFilesConfig,
+	/// `status.showIgnored`
+	pub show_ignored: bool,
+	/// `status.submoduleSummary`
+	pub submodule_summary: bool,
+	/// `status.renames`, falling back to `diff.renames`
+	pub renames: bool,
+	/// `status.relativePaths`; not mapped onto any `StatusOptions` field
+	pub relative_paths: bool,
+	/// whether `core.fsmonitor` is active for this repo; `libgit2` already
+	/// consults `core.fsmonitor` itself when walking the workdir, so this
+	/// isn't mapped onto any `StatusOptions` field - it's surfaced purely
+	/// for callers that schedule *their own* status refreshes (e.g. to
+	/// decide how aggressively to poll) and want to know fsmonitor is
+	/// doing some of that work already
+	pub fsmonitor_enabled: bool,
+}
+
+impl Default for StatusConfig {
+	fn default() -> Self {
+		Self {
+			show_untracked_files:
+				ShowUntrackedFilesConfig::default(),
+			show_ignored: false,
+			submodule_summary: false,
+			renames: false,
+			relative_paths: true,
+			fsmonitor_enabled: false,
+		}
+	}
+}
+
+/// reads every `status.*` setting relevant to a status scan from `repo`
+pub fn status_config_repo(repo: &Repository) -> Result<StatusConfig> {
+	scope_time!("status_config_repo");
+
+	let show_untracked_files = untracked_files_config_repo(repo)?;
+
+	let show_ignored =
+		get_config_bool_repo(repo, "status.showIgnored", true)?
+			.unwrap_or(false);
+
+	// see https://git-scm.com/docs/git-config#Documentation/git-config.txt-statussubmoduleSummary
+	// "Defaults to false" - it walks each submodule's history to build a
+	// commit summary, which is expensive enough that it must stay opt-in
+	let submodule_summary = get_config_bool_repo(
+		repo,
+		"status.submoduleSummary",
+		true,
+	)?
+	.unwrap_or(false);
+
+	let renames = get_config_bool_repo(repo, "status.renames", true)?
+		.or(get_config_bool_repo(repo, "diff.renames", true)?)
+		.unwrap_or(false);
+
+	let relative_paths =
+		get_config_bool_repo(repo, "status.relativePaths", true)?
+			.unwrap_or(true);
+
+	let fsmonitor_enabled = fsmonitor_enabled_repo(repo)?;
+
+	Ok(StatusConfig {
+		show_untracked_files,
+		show_ignored,
+		submodule_summary,
+		renames,
+		relative_paths,
+		fsmonitor_enabled,
+	})
+}
+
+/// builds `git2::StatusOptions` from `repo`'s `status.showUntrackedFiles`,
+/// `status.showIgnored` and `status.renames`/`diff.renames` settings;
+/// `status.submoduleSummary` and `status.relativePaths` are read into
+/// [`StatusConfig`] but do not affect the options built here
+pub fn status_options_from_config_repo(
+	repo: &Repository,
+) -> Result<StatusOptions> {
+	let config = status_config_repo(repo)?;
+
+	let mut options = StatusOptions::new();
+	options
+		.show(StatusShow::IndexAndWorkdir)
+		.update_index(true)
+		.include_untracked(
+			config.show_untracked_files.include_untracked(),
+		)
+		.recurse_untracked_dirs(
+			config.show_untracked_files.recurse_untracked_dirs(),
+		)
+		.include_ignored(config.show_ignored)
+		.renames_head_to_index(config.renames)
+		.renames_index_to_workdir(config.renames);
+
+	Ok(options)
+}
+
+// see https://git-scm.com/docs/git-config#Documentation/git-config.txt-corefsmonitor
+/// checks whether `core.fsmonitor` is active for `repo`
+///
+/// a non-boolean value (e.g. a hook script path) counts as enabled, matching
+/// git's own handling of this setting; `libgit2` already honors this
+/// automatically when it walks the workdir, so there's nothing to toggle on
+/// `StatusOptions` here - this is for callers that want to tune their own
+/// status-refresh scheduling around whether fsmonitor is doing some of
+/// that invalidation work for them
+pub fn fsmonitor_enabled_repo(repo: &Repository) -> Result<bool> {
+	scope_time!("fsmonitor_enabled_repo");
+
+	let cfg = repo.config()?;
+
+	let Ok(entry) = cfg.get_entry("core.fsmonitor") else {
+		return Ok(false);
+	};
+
+	let Some(raw) = entry.value() else {
+		return Ok(false);
+	};
+
+	Ok(parse_config_bool(raw).unwrap_or(true))
+}
+
+/// parses a git config boolean, mirroring `git-config`/gitoxide's own
+/// leniency: `true`/`yes`/`on`/`1` and the empty string map to `true`/`false`
+/// as documented, comparison is ascii-case-insensitive
+fn parse_config_bool(raw: &str) -> Option<bool> {
+	if raw.is_empty() {
+		return Some(false);
+	}
+
+	match raw.to_ascii_lowercase().as_str() {
+		"true" | "yes" | "on" | "1" => Some(true),
+		"false" | "no" | "off" | "0" => Some(false),
+		_ => None,
+	}
+}
+
+/// parses a git config integer, honoring the optional `k`/`m`/`g` suffix
+/// (case-insensitive) that multiplies the value by 1024/1024^2/1024^3
+fn parse_config_int(raw: &str) -> Option<i64> {
+	let raw = raw.trim();
+
+	let (digits, multiplier) = match raw
+		.chars()
+		.last()
+		.map(|c| c.to_ascii_lowercase())
+	{
+		Some('k') => (&raw[..raw.len() - 1], 1024),
+		Some('m') => (&raw[..raw.len() - 1], 1024 * 1024),
+		Some('g') => (&raw[..raw.len() - 1], 1024 * 1024 * 1024),
+		_ => (raw, 1),
+	};
+
+	digits.trim().parse::<i64>().ok()?.checked_mul(multiplier)
+}
+
+/// get a boolean from config, falling back to `repo_path`'s repo config
+///
+/// when `lenient` is `true` a malformed value yields `Ok(None)` instead of
+/// an error, matching how most callers just want a best-effort default
+pub fn get_config_bool(
+	repo_path: &RepoPath,
+	key: &str,
+	lenient: bool,
+) -> Result<Option<bool>> {
+	let repo = repo(repo_path)?;
+	get_config_bool_repo(&repo, key, lenient)
+}
+
+///
+pub fn get_config_bool_repo(
+	repo: &Repository,
+	key: &str,
+	lenient: bool,
+) -> Result<Option<bool>> {
+	scope_time!("get_config_bool_repo");
+
+	let cfg = repo.config()?;
+
+	let Ok(entry) = cfg.get_entry(key) else {
+		return Ok(None);
+	};
+
+	// a valueless key, e.g. `[section]\n\tflag`, is `true`
+	let Some(raw) = entry.value() else {
+		return Ok(Some(true));
+	};
+
+	match parse_config_bool(raw) {
+		Some(value) => Ok(Some(value)),
+		None if lenient => Ok(None),
+		None => Err(crate::Error::GitConfig(format!(
+			"malformed boolean value for {key}: {raw}"
+		))),
+	}
+}
+
+/// get an integer from config, falling back to `repo_path`'s repo config
+///
+/// when `lenient` is `true` a malformed value yields `Ok(None)` instead of
+/// an error
+pub fn get_config_int(
+	repo_path: &RepoPath,
+	key: &str,
+	lenient: bool,
+) -> Result<Option<i64>> {
+	let repo = repo(repo_path)?;
+	get_config_int_repo(&repo, key, lenient)
+}
+
+///
+pub fn get_config_int_repo(
+	repo: &Repository,
+	key: &str,
+	lenient: bool,
+) -> Result<Option<i64>> {
+	scope_time!("get_config_int_repo");
+
+	let cfg = repo.config()?;
+
+	let Ok(entry) = cfg.get_entry(key) else {
+		return Ok(None);
+	};
+
+	let Some(raw) = entry.value() else {
+		return Ok(None);
+	};
+
+	match parse_config_int(raw) {
+		Some(value) => Ok(Some(value)),
+		None if lenient => Ok(None),
+		None => Err(crate::Error::GitConfig(format!(
+			"malformed integer value for {key}: {raw}"
+		))),
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -177,4 +851,429 @@ mod tests {
 		assert!(good_cfg.is_ok());
 		assert!(good_cfg.unwrap().is_some());
 	}
+
+	#[test]
+	fn test_parse_config_bool() {
+		assert_eq!(parse_config_bool("true"), Some(true));
+		assert_eq!(parse_config_bool("Yes"), Some(true));
+		assert_eq!(parse_config_bool("ON"), Some(true));
+		assert_eq!(parse_config_bool("1"), Some(true));
+		assert_eq!(parse_config_bool("false"), Some(false));
+		assert_eq!(parse_config_bool("No"), Some(false));
+		assert_eq!(parse_config_bool("off"), Some(false));
+		assert_eq!(parse_config_bool("0"), Some(false));
+		assert_eq!(parse_config_bool(""), Some(false));
+		assert_eq!(parse_config_bool("bogus"), None);
+	}
+
+	#[test]
+	fn test_parse_config_int() {
+		assert_eq!(parse_config_int("42"), Some(42));
+		assert_eq!(parse_config_int("1k"), Some(1024));
+		assert_eq!(parse_config_int("2K"), Some(2048));
+		assert_eq!(parse_config_int("1m"), Some(1024 * 1024));
+		assert_eq!(parse_config_int("1g"), Some(1024 * 1024 * 1024));
+		assert_eq!(parse_config_int("not-a-number"), None);
+		// must not panic/wrap on overflow of the `* multiplier`
+		assert_eq!(
+			parse_config_int("9223372036854775807g"),
+			None
+		);
+	}
+
+	#[test]
+	fn test_get_config_bool_lenient_vs_strict() {
+		let (_td, repo) = repo_init().unwrap();
+		let mut cfg = repo.config().unwrap();
+		cfg.set_str("test.weird", "maybe").unwrap();
+
+		let lenient =
+			get_config_bool_repo(&repo, "test.weird", true).unwrap();
+		assert_eq!(lenient, None);
+
+		let strict =
+			get_config_bool_repo(&repo, "test.weird", false);
+		assert!(strict.is_err());
+	}
+
+	#[test]
+	fn test_set_and_unset_config_string_local() {
+		let (_td, repo) = repo_init().unwrap();
+
+		set_config_string_repo(
+			&repo,
+			"user.email",
+			"local-override@example.com",
+			ConfigScope::Local,
+		)
+		.unwrap();
+
+		let entry =
+			get_config_entry(&repo, "user.email").unwrap().unwrap();
+		assert_eq!(entry.value, "local-override@example.com");
+		assert_eq!(entry.scope, Some(ConfigScope::Local));
+
+		unset_config_string_repo(
+			&repo,
+			"user.email",
+			ConfigScope::Local,
+		)
+		.unwrap();
+
+		let entry = get_config_entry(&repo, "user.email").unwrap();
+		assert_ne!(
+			entry.and_then(|e| e.scope),
+			Some(ConfigScope::Local)
+		);
+	}
+
+	#[test]
+	fn test_fsmonitor_enabled_repo() {
+		let (_td, repo) = repo_init().unwrap();
+
+		assert!(!fsmonitor_enabled_repo(&repo).unwrap());
+
+		let mut cfg = repo.config().unwrap();
+		cfg.set_str("core.fsmonitor", "true").unwrap();
+		assert!(fsmonitor_enabled_repo(&repo).unwrap());
+
+		cfg.set_str("core.fsmonitor", "false").unwrap();
+		assert!(!fsmonitor_enabled_repo(&repo).unwrap());
+
+		// a hook path counts as enabled
+		cfg.set_str("core.fsmonitor", ".git/hooks/fsmonitor-watchman")
+			.unwrap();
+		assert!(fsmonitor_enabled_repo(&repo).unwrap());
+	}
+
+	#[test]
+	fn test_status_config_defaults() {
+		let (_td, repo) = repo_init().unwrap();
+
+		let config = status_config_repo(&repo).unwrap();
+		assert!(!config.show_ignored);
+		assert!(!config.renames);
+		// status.submoduleSummary defaults to false upstream because it
+		// walks each submodule's history to build a commit summary
+		assert!(!config.submodule_summary);
+		assert!(!config.fsmonitor_enabled);
+
+		let options = status_options_from_config_repo(&repo);
+		assert!(options.is_ok());
+	}
+
+	#[test]
+	fn test_status_config_surfaces_fsmonitor_enabled() {
+		let (_td, repo) = repo_init().unwrap();
+
+		assert!(!status_config_repo(&repo).unwrap().fsmonitor_enabled);
+
+		let mut cfg = repo.config().unwrap();
+		cfg.set_str("core.fsmonitor", "true").unwrap();
+
+		assert!(status_config_repo(&repo).unwrap().fsmonitor_enabled);
+		// fsmonitor state doesn't change what StatusOptions we build -
+		// `libgit2` consults `core.fsmonitor` itself
+		assert!(status_options_from_config_repo(&repo).is_ok());
+	}
+
+	#[test]
+	fn test_status_config_renames_falls_back_to_diff_renames() {
+		let (_td, repo) = repo_init().unwrap();
+
+		let mut cfg = repo.config().unwrap();
+		cfg.set_str("diff.renames", "true").unwrap();
+
+		let config = status_config_repo(&repo).unwrap();
+		assert!(config.renames);
+	}
+
+	#[test]
+	fn test_resolve_push_refspec_nothing_errors() {
+		let (_td, repo) = repo_init().unwrap();
+
+		let mut cfg = repo.config().unwrap();
+		cfg.set_str("push.default", "nothing").unwrap();
+
+		assert!(resolve_push_refspec(&repo, "origin").is_err());
+	}
+
+	#[test]
+	fn test_resolve_push_refspec_current() {
+		let (_td, repo) = repo_init().unwrap();
+
+		let mut cfg = repo.config().unwrap();
+		cfg.set_str("push.default", "current").unwrap();
+
+		let branch =
+			repo.head().unwrap().shorthand().unwrap().to_string();
+
+		let refspec =
+			resolve_push_refspec(&repo, "origin").unwrap().unwrap();
+
+		assert_eq!(
+			refspec,
+			PushRefspec::Single(PushRefspecPair {
+				local_ref: format!("refs/heads/{branch}"),
+				remote_ref: format!("refs/heads/{branch}"),
+				force: false,
+			})
+		);
+	}
+
+	#[test]
+	fn test_resolve_push_refspec_upstream_wrong_remote_errors() {
+		let (_td, repo) = repo_init().unwrap();
+
+		let branch =
+			repo.head().unwrap().shorthand().unwrap().to_string();
+
+		let mut cfg = repo.config().unwrap();
+		cfg.set_str("push.default", "upstream").unwrap();
+		cfg.set_str(&format!("branch.{branch}.remote"), "origin")
+			.unwrap();
+		cfg.set_str(
+			&format!("branch.{branch}.merge"),
+			&format!("refs/heads/{branch}"),
+		)
+		.unwrap();
+
+		assert!(resolve_push_refspec(&repo, "upstream").is_err());
+
+		let refspec =
+			resolve_push_refspec(&repo, "origin").unwrap().unwrap();
+		assert_eq!(
+			refspec,
+			PushRefspec::Single(PushRefspecPair {
+				local_ref: format!("refs/heads/{branch}"),
+				remote_ref: format!("refs/heads/{branch}"),
+				force: false,
+			})
+		);
+	}
+
+	#[test]
+	fn test_resolve_push_refspec_simple_falls_back_to_current() {
+		let (_td, repo) = repo_init().unwrap();
+
+		let branch =
+			repo.head().unwrap().shorthand().unwrap().to_string();
+
+		let mut cfg = repo.config().unwrap();
+		cfg.set_str("push.default", "simple").unwrap();
+		// not tracking "origin" at all
+		cfg.set_str(&format!("branch.{branch}.remote"), "other")
+			.unwrap();
+		cfg.set_str(
+			&format!("branch.{branch}.merge"),
+			&format!("refs/heads/{branch}"),
+		)
+		.unwrap();
+
+		let refspec =
+			resolve_push_refspec(&repo, "origin").unwrap().unwrap();
+
+		assert_eq!(
+			refspec,
+			PushRefspec::Single(PushRefspecPair {
+				local_ref: format!("refs/heads/{branch}"),
+				remote_ref: format!("refs/heads/{branch}"),
+				force: false,
+			})
+		);
+	}
+
+	#[test]
+	fn test_resolve_push_refspec_simple_name_mismatch_errors() {
+		let (_td, repo) = repo_init().unwrap();
+
+		let branch =
+			repo.head().unwrap().shorthand().unwrap().to_string();
+
+		let mut cfg = repo.config().unwrap();
+		cfg.set_str("push.default", "simple").unwrap();
+		cfg.set_str(&format!("branch.{branch}.remote"), "origin")
+			.unwrap();
+		cfg.set_str(
+			&format!("branch.{branch}.merge"),
+			"refs/heads/a-different-name",
+		)
+		.unwrap();
+
+		assert!(resolve_push_refspec(&repo, "origin").is_err());
+	}
+
+	#[test]
+	fn test_resolve_push_refspec_simple_success() {
+		let (_td, repo) = repo_init().unwrap();
+
+		let branch =
+			repo.head().unwrap().shorthand().unwrap().to_string();
+
+		let mut cfg = repo.config().unwrap();
+		cfg.set_str("push.default", "simple").unwrap();
+		cfg.set_str(&format!("branch.{branch}.remote"), "origin")
+			.unwrap();
+		cfg.set_str(
+			&format!("branch.{branch}.merge"),
+			&format!("refs/heads/{branch}"),
+		)
+		.unwrap();
+
+		let refspec =
+			resolve_push_refspec(&repo, "origin").unwrap().unwrap();
+
+		assert_eq!(
+			refspec,
+			PushRefspec::Single(PushRefspecPair {
+				local_ref: format!("refs/heads/{branch}"),
+				remote_ref: format!("refs/heads/{branch}"),
+				force: false,
+			})
+		);
+	}
+
+	#[test]
+	fn test_resolve_push_refspec_matching() {
+		let (_td, repo) = repo_init().unwrap();
+
+		let mut cfg = repo.config().unwrap();
+		cfg.set_str("push.default", "matching").unwrap();
+
+		let branch =
+			repo.head().unwrap().shorthand().unwrap().to_string();
+		let head_oid = repo.head().unwrap().target().unwrap();
+
+		// simulate a remote-tracking branch for the current branch...
+		repo.reference(
+			&format!("refs/remotes/origin/{branch}"),
+			head_oid,
+			true,
+			"fake remote-tracking ref for test",
+		)
+		.unwrap();
+		// ...and a local-only branch that has no remote counterpart
+		repo.branch(
+			"local-only",
+			&repo.find_commit(head_oid).unwrap(),
+			false,
+		)
+		.unwrap();
+
+		let refspec =
+			resolve_push_refspec(&repo, "origin").unwrap().unwrap();
+
+		assert_eq!(
+			refspec,
+			PushRefspec::Matching(vec![PushRefspecPair {
+				local_ref: format!("refs/heads/{branch}"),
+				remote_ref: format!("refs/heads/{branch}"),
+				force: false,
+			}])
+		);
+	}
+
+	#[test]
+	fn test_config_snapshot() {
+		let (_td, repo) = repo_init().unwrap();
+
+		let mut cfg = repo.config().unwrap();
+		cfg.set_str("user.email", "snapshot@example.com").unwrap();
+		cfg.set_bool("core.bare", false).unwrap();
+		cfg.set_i64("core.repositoryformatversion", 0).unwrap();
+
+		let snapshot = ConfigSnapshot::new(&repo).unwrap();
+
+		assert_eq!(
+			snapshot.string("user.email"),
+			Some("snapshot@example.com")
+		);
+		// lookups are case-insensitive, like git's own key matching
+		assert_eq!(
+			snapshot.string("USER.EMAIL"),
+			Some("snapshot@example.com")
+		);
+		assert_eq!(snapshot.bool("core.bare"), Some(false));
+		assert_eq!(
+			snapshot.int("core.repositoryformatversion"),
+			Some(0)
+		);
+		assert_eq!(snapshot.string("this.doesnt.exist"), None);
+
+		cfg.set_str("user.email", "changed@example.com").unwrap();
+		// a stale snapshot keeps returning the value it was loaded with
+		assert_eq!(
+			snapshot.string("user.email"),
+			Some("snapshot@example.com")
+		);
+	}
+
+	#[test]
+	fn test_config_snapshot_matches_get_entry_precedence() {
+		let (td, _repo) = repo_init().unwrap();
+
+		// an isolated multi-level config, so this doesn't touch any real
+		// system/global file on the machine running the test
+		let system_path = td.path().join("system.gitconfig");
+		let local_path = td.path().join("local.gitconfig");
+
+		let mut multi = Config::new().unwrap();
+		multi
+			.add_file(&system_path, git2::ConfigLevel::System, false)
+			.unwrap();
+		multi
+			.add_file(&local_path, git2::ConfigLevel::Local, false)
+			.unwrap();
+
+		multi
+			.open_level(git2::ConfigLevel::System)
+			.unwrap()
+			.set_str("example.value", "from-system")
+			.unwrap();
+		multi
+			.open_level(git2::ConfigLevel::Local)
+			.unwrap()
+			.set_str("example.value", "from-local")
+			.unwrap();
+
+		let effective =
+			multi.get_entry("example.value").unwrap();
+		assert_eq!(effective.value(), Some("from-local"));
+
+		let snapshot = ConfigSnapshot::from_config(&multi).unwrap();
+		assert_eq!(snapshot.string("example.value"), effective.value());
+	}
+
+	#[test]
+	fn test_normalize_config_key_case_preserves_subsection() {
+		assert_eq!(
+			normalize_config_key_case("Core.Bare"),
+			"core.bare"
+		);
+		assert_eq!(
+			normalize_config_key_case("Branch.Main.Remote"),
+			"branch.Main.remote"
+		);
+	}
+
+	#[test]
+	fn test_config_snapshot_subsection_is_case_sensitive() {
+		let (_td, repo) = repo_init().unwrap();
+
+		let mut cfg = repo.config().unwrap();
+		cfg.set_str("branch.Main.remote", "origin-main").unwrap();
+		cfg.set_str("branch.main.remote", "origin-lowercase")
+			.unwrap();
+
+		let snapshot = ConfigSnapshot::new(&repo).unwrap();
+
+		assert_eq!(
+			snapshot.string("branch.Main.remote"),
+			Some("origin-main")
+		);
+		assert_eq!(
+			snapshot.string("branch.main.remote"),
+			Some("origin-lowercase")
+		);
+	}
 }